@@ -5,14 +5,214 @@ use polars::prelude::*;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use pyo3::wrap_pyfunction;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 // No longer needed
 
+/// Upper bound on how many A-only / B-only residual rows `similarity_threshold`
+/// will materialize per side before candidate pairing -- keeps the fuzzy pass
+/// bounded even when the two datasets are almost entirely disjoint.
+const MAX_FUZZY_RESIDUAL_ROWS: u32 = 20_000;
+
+/// An "equal enough" rule for one column, as passed in via `tolerances`.
+///
+/// Accepted from Python as: a float (`Absolute`), a 2-tuple of floats
+/// (`AbsRelative`, `(atol, rtol)`), or one of the strings `"trim"`/
+/// `"ignore_case"` (`StringNormalize`).
+#[derive(Clone)]
+enum ToleranceRule {
+    Absolute(f64),
+    AbsRelative(f64, f64),
+    StringNormalize { trim: bool, ignore_case: bool },
+}
+
+impl<'py> FromPyObject<'py> for ToleranceRule {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok((atol, rtol)) = ob.extract::<(f64, f64)>() {
+            return Ok(ToleranceRule::AbsRelative(atol, rtol));
+        }
+        if let Ok(v) = ob.extract::<f64>() {
+            return Ok(ToleranceRule::Absolute(v));
+        }
+        if let Ok(s) = ob.extract::<String>() {
+            return match s.as_str() {
+                "ignore_case" => Ok(ToleranceRule::StringNormalize {
+                    trim: true,
+                    ignore_case: true,
+                }),
+                "trim" => Ok(ToleranceRule::StringNormalize {
+                    trim: true,
+                    ignore_case: false,
+                }),
+                other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown tolerance rule {:?}: expected a float, an (atol, rtol) tuple, or \"trim\"/\"ignore_case\"",
+                    other
+                ))),
+            };
+        }
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "tolerance rule must be a float, an (atol, rtol) tuple, or a string",
+        ))
+    }
+}
+
+/// Builds the per-row mismatch expression for one shared column, applying
+/// `rule` (if any) instead of the default strict `eq_missing`.
+fn build_is_diff_expr(
+    name_str: &str,
+    right_name: &str,
+    dtype_a: &DataType,
+    dtype_b: &DataType,
+    rule: Option<&ToleranceRule>,
+) -> Expr {
+    if dtype_a.is_numeric() && dtype_b.is_numeric() {
+        let a = col(name_str).cast(DataType::Float64);
+        let b = col(right_name).cast(DataType::Float64);
+        match rule {
+            Some(ToleranceRule::Absolute(atol)) => (a - b).abs().gt(lit(*atol)),
+            Some(ToleranceRule::AbsRelative(atol, rtol)) => {
+                (a.clone() - b.clone()).abs().gt(lit(*atol) + lit(*rtol) * b.abs())
+            }
+            _ => col(name_str).eq_missing(col(right_name)).not(),
+        }
+    } else if let Some(ToleranceRule::StringNormalize { trim, ignore_case }) = rule {
+        let mut a = col(name_str).cast(DataType::String);
+        let mut b = col(right_name).cast(DataType::String);
+        if *trim {
+            a = a.str().strip_chars(lit(NULL));
+            b = b.str().strip_chars(lit(NULL));
+        }
+        if *ignore_case {
+            a = a.str().to_lowercase();
+            b = b.str().to_lowercase();
+        }
+        a.eq_missing(b).not()
+    } else {
+        col(name_str).eq_missing(col(right_name)).not()
+    }
+}
+
+/// Builds the full row-level diff `LazyFrame` used by `output_path`: every
+/// key row from the inner join (`matched`), the A-only anti-join (`removed`),
+/// and the B-only anti-join (`added`), each tagged with a `_change_type` and
+/// diagonally concatenated so columns unique to one side still appear
+/// (filled with null elsewhere) instead of requiring identical schemas.
+/// Pulled out of `diff_files` so it's testable without a real file scan.
+fn build_diff_export_lf(
+    joined_lf: LazyFrame,
+    lf_a: LazyFrame,
+    lf_b: LazyFrame,
+    schema_a: &Schema,
+    schema_b: &Schema,
+    keys: &[Expr],
+    keys_strs: &[&str],
+    total_modified_mask: Option<Expr>,
+) -> PolarsResult<LazyFrame> {
+    let non_key_cols_a: Vec<&str> = schema_a
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !keys_strs.contains(name))
+        .collect();
+    let non_key_cols_b: Vec<&str> = schema_b
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !keys_strs.contains(name))
+        .collect();
+
+    // Matched rows: every shared non-key column becomes a `_a`/`_b` pair,
+    // and `_change_type` is derived from whether any of them differ. This
+    // iterates the union of both schemas' non-key columns -- a column
+    // introduced only in B (schema drift) still needs to show up as
+    // `{col}_b` on every row, not just the handful of `added` ones.
+    let mut matched_select = keys.to_vec();
+    for name_str in &non_key_cols_a {
+        matched_select.push(col(*name_str).alias(&format!("{}_a", name_str)));
+        if schema_b.contains(name_str) {
+            matched_select
+                .push(col(&format!("{}_right", name_str)).alias(&format!("{}_b", name_str)));
+        }
+    }
+    for name_str in &non_key_cols_b {
+        if !schema_a.contains(name_str) {
+            // Unique to B, so the join left it unsuffixed.
+            matched_select.push(col(*name_str).alias(&format!("{}_b", name_str)));
+        }
+    }
+    // The mask refers to the joined frame's own column names (`{col}` and
+    // `{col}_right`), so it is evaluated in the same select, before the
+    // `_a`/`_b` renames take effect.
+    matched_select.push(
+        when(total_modified_mask.unwrap_or_else(|| lit(false)))
+            .then(lit("modified"))
+            .otherwise(lit("identical"))
+            .alias("_change_type"),
+    );
+    let matched_output = joined_lf.select(matched_select);
+
+    // Removed rows: keys present in A but not B (left-anti join).
+    let mut removed_select = keys.to_vec();
+    for name_str in &non_key_cols_a {
+        removed_select.push(col(*name_str).alias(&format!("{}_a", name_str)));
+    }
+    let removed_output = lf_a
+        .clone()
+        .join(
+            lf_b.clone(),
+            keys.to_vec(),
+            keys.to_vec(),
+            JoinArgs::new(JoinType::Anti),
+        )
+        .select(removed_select)
+        .with_column(lit("removed").alias("_change_type"));
+
+    // Added rows: keys present in B but not A (right-anti join, i.e. an
+    // anti-join of B against A).
+    let mut added_select = keys.to_vec();
+    for name_str in &non_key_cols_b {
+        added_select.push(col(*name_str).alias(&format!("{}_b", name_str)));
+    }
+    let added_output = lf_b
+        .join(lf_a, keys.to_vec(), keys.to_vec(), JoinArgs::new(JoinType::Anti))
+        .select(added_select)
+        .with_column(lit("added").alias("_change_type"));
+
+    // Column sets differ between the three frames (e.g. only matched
+    // rows have both `_a` and `_b` values for a column that's missing on
+    // one side) -- a diagonal concat aligns by name and fills the rest
+    // with nulls instead of requiring identical schemas.
+    concat(
+        [matched_output, added_output, removed_output],
+        UnionArgs {
+            diagonal: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// Whether `path` should be scanned as a single hive-partitioned Parquet
+/// dataset rather than a plain file: a directory, or a glob pattern whose
+/// extension is `.parquet`/`.pq`. A glob over a non-Parquet extension (e.g.
+/// `"events_*.csv"`) must return `false` here so it still falls through to
+/// the CSV/JSON readers in `scan_df`.
+fn is_partitioned_dataset(path: &str) -> bool {
+    Path::new(path).is_dir()
+        || ((path.contains('*') || path.contains('?'))
+            && (path.ends_with(".parquet") || path.ends_with(".pq")))
+}
+
 /// Compares two CSV or Parquet files and returns a difference summary
 ///
 /// Args:
-///     file_a (str): Path to first file
-///     file_b (str): Path to second file
+///     file_a (str): Path to first file. May also be a directory of Parquet
+///         files or a glob pattern (e.g. `"data/dt=*/region=*/*.parquet"`);
+///         in that case it's scanned as a single hive-partitioned dataset and
+///         the partition keys are surfaced as real columns.
+///     file_b (str): Path to second file. Same rules as `file_a`.
 ///     key_cols (list[str]): Columns to join on
+///     partition_cols (list[str] | None): If given, also break the summary
+///         down per distinct combination of these partition columns (see
+///         `partition_stats` below). Typically the same columns that were
+///         derived from hive partitioning in `file_a`/`file_b`.
 ///
 /// Returns:
 ///     dict: {
@@ -21,20 +221,91 @@ use pyo3::wrap_pyfunction;
 ///         "matched": int,
 ///         "added": int,
 ///         "removed": int,
+///         "added_sample_keys": list[dict],    // New! first ~50 added key rows
+///         "removed_sample_keys": list[dict],  // New! first ~50 removed key rows
 ///         "modified_cols": list[str],
 ///         "schema_diff": list[dict],  // New!
 ///         "null_counts": dict,        // New! { "col_name": [nulls_in_a, nulls_in_b] }
+///         "partition_stats": list[dict] | None,  // New! per-partition added/removed/modified
+///         "window_diffs": list[dict] | None,  // New! time-bucketed deltas, see `time_col`
+///         "fuzzy_reclassified_count": int,  // New! adds+removes promoted to modified, see `similarity_threshold`
 ///     }
+///
+/// Temporal-windowed mode:
+///     Passing `time_col` additionally bucketizes both frames with
+///     `group_by_dynamic` (`window_every`/`window_period`/`window_offset`,
+///     polars duration strings like `"1h"`) and diffs the two window tables
+///     alongside the row-level join, which always runs. `window_closed` picks
+///     which side of each window is inclusive (`"left"` (default), `"right"`,
+///     `"both"`, or `"none"`) — keep it `"left"` or `"both"` so a timestamp
+///     that lands exactly on the first window boundary is never dropped.
+///
+/// `output_path`, if given, streams the complete row-level diff (not just the
+/// 100-row sample) to a Parquet/CSV/NDJSON file, format inferred from the
+/// extension like `scan_df`. Every row carries a `_change_type` column
+/// (`"added"`/`"removed"`/`"modified"`/`"identical"`); modified rows carry
+/// both the `_a` and `_b` value of every non-key column present in both
+/// files (a column found in only one file appears only as `_a` or `_b`).
+///
+/// `similarity_threshold`, if given, runs a fuzzy-matching pass over the rows
+/// that failed the exact key join: residual A-only and B-only rows sharing
+/// the same `block_col` value (or, if unset, the same first key column) are
+/// paired and scored on the fraction of non-key columns that are "equal
+/// enough" (normalized edit distance for strings, relative closeness for
+/// numerics). Pairs scoring at or above the threshold are greedily matched
+/// (highest score first, each row used at most once) and reclassified from
+/// added+removed to modified; `fuzzy_reclassified_count` reports how many
+/// pairs this recovered. Residual rows are capped at `MAX_FUZZY_RESIDUAL_ROWS`
+/// per side, and this reclassification is summary-only: it is not reflected
+/// in `output_path`'s exported `_change_type` column (see the warning printed
+/// when both are set).
+///
+/// `tolerances` maps a column name to an "equal enough" rule, instead of the
+/// default strict `eq_missing`: a float is an absolute epsilon, a 2-tuple
+/// `(atol, rtol)` is `abs(a - b) > atol + rtol * abs(b)`, and the strings
+/// `"trim"` (strip whitespace) / `"ignore_case"` (strip whitespace and
+/// lowercase) normalize string columns before comparing. `max_value_diff` still reports the true, un-tolerated
+/// magnitude.
 #[pyfunction]
 fn diff_files<'py>(
     py: Python<'py>,
     file_a: String,
     file_b: String,
     _key_cols: Vec<String>,
+    partition_cols: Option<Vec<String>>,
+    time_col: Option<String>,
+    window_every: Option<String>,
+    window_period: Option<String>,
+    window_offset: Option<String>,
+    window_closed: Option<String>,
+    output_path: Option<String>,
+    similarity_threshold: Option<f64>,
+    block_col: Option<String>,
+    tolerances: Option<HashMap<String, ToleranceRule>>,
 ) -> PyResult<Bound<'py, PyDict>> {
     // 1. Read files lazily using Polars
     let scan_df = |path: &str| -> PyResult<LazyFrame> {
-        if path.ends_with(".parquet") || path.ends_with(".pq") {
+        // Directories and Parquet glob patterns are scanned as a single
+        // hive-partitioned dataset so that `dt=.../region=.../part-0.parquet`-style
+        // path segments surface as real, queryable columns instead of being
+        // discarded. A glob over a non-Parquet extension (e.g. `"events_*.csv"`)
+        // is left alone so it still falls through to the CSV/JSON readers below.
+        if is_partitioned_dataset(path) {
+            let pattern = if Path::new(path).is_dir() {
+                format!("{}/**/*.parquet", path.trim_end_matches('/'))
+            } else {
+                path.to_string()
+            };
+            let args = ScanArgsParquet {
+                hive_options: HiveOptions {
+                    enabled: Some(true),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            LazyFrame::scan_parquet(pattern.into(), args)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
+        } else if path.ends_with(".parquet") || path.ends_with(".pq") {
             LazyFrame::scan_parquet(path.into(), Default::default())
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
         } else if path.ends_with(".jsonl") || path.ends_with(".ndjson") {
@@ -82,9 +353,23 @@ fn diff_files<'py>(
 
     // 2.2 Pre-Calculation: Height and Uniqueness (Small passes)
     // We don't use streaming here because these are lightweight and streaming adds overhead for small files
-    let get_meta = |lf: LazyFrame, name: &str, key: &str| -> PyResult<(usize, usize)> {
+    // Uniqueness is measured on the *composite* key (all of `keys`, concatenated),
+    // not just the first column -- otherwise a genuinely unique multi-column key
+    // looks duplicated and can trip the Cartesian-product guard below.
+    // Each key part is cast to a string and a null is replaced by a "\u{0}"
+    // placeholder before concatenating: dropping nulls would let two rows
+    // with a null in *different* key positions (but matching non-null parts)
+    // collapse onto the same composite string, and a null composite would
+    // make every null-bearing key count as one value, so either way they'd
+    // be flagged as duplicates of each other.
+    let get_meta = |lf: LazyFrame, name: &str, key_exprs: Vec<Expr>| -> PyResult<(usize, usize)> {
+        let key_parts: Vec<Expr> = key_exprs
+            .into_iter()
+            .map(|e| e.cast(DataType::String).fill_null(lit("\u{0}")))
+            .collect();
+        let composite_key = concat_str(key_parts, "\u{1f}", false);
         let res = lf
-            .select([len().alias("total"), col(key).n_unique().alias("unique")])
+            .select([len().alias("total"), composite_key.n_unique().alias("unique")])
             .collect()
             .map_err(|e| {
                 PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
@@ -117,8 +402,8 @@ fn diff_files<'py>(
         Ok((total, unique))
     };
 
-    let (height_a, unique_a) = get_meta(lf_a.clone(), "File A", keys_strs[0])?;
-    let (height_b, unique_b) = get_meta(lf_b.clone(), "File B", keys_strs[0])?;
+    let (height_a, unique_a) = get_meta(lf_a.clone(), "File A", keys.clone())?;
+    let (height_b, unique_b) = get_meta(lf_b.clone(), "File B", keys.clone())?;
 
     // 2.2.1 Join Safety Guard (Cartesian Product Estimation)
     // If keys are not unique, the worst case join size is (non-unique_a * non-unique_b)
@@ -156,7 +441,8 @@ fn diff_files<'py>(
         if schema_b.contains(name_str) {
             let right_name = format!("{}_right", name_str);
             let dtype_b = schema_b.get(name_str).unwrap();
-            let is_diff_expr = col(name_str).eq_missing(col(&right_name)).not();
+            let rule = tolerances.as_ref().and_then(|t| t.get(name_str));
+            let is_diff_expr = build_is_diff_expr(name_str, &right_name, dtype_a, dtype_b, rule);
             aggs.push(
                 is_diff_expr
                     .clone()
@@ -217,7 +503,7 @@ fn diff_files<'py>(
         .unwrap()
         .try_extract::<f64>()
         .unwrap_or(0.0) as usize;
-    let modified_rows_count = if total_modified_mask.is_some() {
+    let mut modified_rows_count = if total_modified_mask.is_some() {
         stats_res
             .column("_total_modified")
             .unwrap()
@@ -229,15 +515,195 @@ fn diff_files<'py>(
         0
     };
 
-    let removed = height_a.saturating_sub(matched);
-    let added = height_b.saturating_sub(matched);
+    let mut removed = height_a.saturating_sub(matched);
+    let mut added = height_b.saturating_sub(matched);
     let identical_rows_count = matched.saturating_sub(modified_rows_count);
 
+    // 2.3.2 Fuzzy Residual Matching (optional)
+    // Rows that missed the exact key join aren't necessarily real adds/removes
+    // -- a typo'd or drifted key can make the "same" record look like one of
+    // each. Pair up residuals within cheap blocks and promote high-similarity
+    // pairs from added+removed to modified.
+    //
+    // NOTE: this only affects the summary counters below. `output_path`
+    // (chunk0-3) streams its `_change_type` column straight from the raw
+    // anti-joins and does not know about fuzzy pairs, so enabling both at
+    // once will disagree with this summary about which rows are
+    // added/removed vs. modified -- warn loudly rather than silently
+    // shipping two inconsistent views of the same diff.
+    if output_path.is_some() && similarity_threshold.is_some() {
+        println!(
+            "⚠️ WARNING: output_path does not reflect similarity_threshold reclassification; \
+            rows this summary counts as 'modified' via fuzzy matching are still tagged \
+            'added'/'removed' in the exported diff."
+        );
+    }
+
+    // Rows fuzzy-reclassified as `modified` below still exist in the raw
+    // anti-join residual, so the added/removed key samples need to exclude
+    // them too -- otherwise a key listed in `removed_sample_keys` could be
+    // reported as `removed == 0` at the same time. Populated inside the
+    // `similarity_threshold` branch below.
+    let mut fuzzy_matched_removed_keys: Option<DataFrame> = None;
+    let mut fuzzy_matched_added_keys: Option<DataFrame> = None;
+
+    let fuzzy_reclassified_count = if let Some(threshold) = similarity_threshold {
+        let compare_cols: Vec<(String, DataType)> = schema_a
+            .iter()
+            .filter(|(name, _)| !keys_strs.contains(&name.as_str()) && schema_b.contains(name.as_str()))
+            .map(|(name, dtype)| (name.to_string(), dtype.clone()))
+            .collect();
+
+        // Bound how many residual rows we materialize: fuzzy matching is meant
+        // to help when the two datasets are mostly disjoint, which is exactly
+        // when an uncapped anti-join residual can be (close to) the whole
+        // dataset. `block_col` already bounds candidate *pairs*; this bounds
+        // how many candidate *rows* we collect in the first place.
+        let removed_full_df = lf_a
+            .clone()
+            .join(
+                lf_b.clone(),
+                keys.clone(),
+                keys.clone(),
+                JoinArgs::new(JoinType::Anti),
+            )
+            .limit(MAX_FUZZY_RESIDUAL_ROWS)
+            .collect()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        let added_full_df = lf_b
+            .clone()
+            .join(
+                lf_a.clone(),
+                keys.clone(),
+                keys.clone(),
+                JoinArgs::new(JoinType::Anti),
+            )
+            .limit(MAX_FUZZY_RESIDUAL_ROWS)
+            .collect()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        if removed_full_df.height() as u32 == MAX_FUZZY_RESIDUAL_ROWS {
+            println!(
+                "⚠️ WARNING: File A's residual (unmatched) rows were truncated to {} for fuzzy \
+                matching; some removed rows may not get a chance to match.",
+                MAX_FUZZY_RESIDUAL_ROWS
+            );
+        }
+        if added_full_df.height() as u32 == MAX_FUZZY_RESIDUAL_ROWS {
+            println!(
+                "⚠️ WARNING: File B's residual (unmatched) rows were truncated to {} for fuzzy \
+                matching; some added rows may not get a chance to match.",
+                MAX_FUZZY_RESIDUAL_ROWS
+            );
+        }
+
+        let block_column = block_col.as_deref().unwrap_or(keys_strs[0]);
+        if !schema_a.contains(block_column) || !schema_b.contains(block_column) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "block_col {:?} is not a column in both File A and File B",
+                block_column
+            )));
+        }
+        let fuzzy = fuzzy_match_residuals(
+            &removed_full_df,
+            &added_full_df,
+            &compare_cols,
+            block_column,
+            threshold,
+        )?;
+
+        removed = removed.saturating_sub(fuzzy.reclassified);
+        added = added.saturating_sub(fuzzy.reclassified);
+        modified_rows_count += fuzzy.reclassified;
+
+        if !fuzzy.removed_idx.is_empty() {
+            fuzzy_matched_removed_keys = Some(
+                key_subset_df(&removed_full_df, &fuzzy.removed_idx, &keys_strs)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+            );
+        }
+        if !fuzzy.added_idx.is_empty() {
+            fuzzy_matched_added_keys = Some(
+                key_subset_df(&added_full_df, &fuzzy.added_idx, &keys_strs)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+            );
+        }
+
+        fuzzy.reclassified
+    } else {
+        0
+    };
+
+    // 2.3.3 Added / Removed Key Samples
+    // The counts alone don't tell a user *which* keys vanished or showed up;
+    // anti-join against the other side and hand back the first ~50 of each.
+    // Keys that fuzzy matching (above) reclassified as `modified` are
+    // anti-joined back out so the samples agree with `added`/`removed`.
+    let mut removed_residual_lf = lf_a.clone().join(
+        lf_b.clone(),
+        keys.clone(),
+        keys.clone(),
+        JoinArgs::new(JoinType::Anti),
+    );
+    if let Some(matched_keys) = &fuzzy_matched_removed_keys {
+        removed_residual_lf = removed_residual_lf.join(
+            matched_keys.clone().lazy(),
+            keys.clone(),
+            keys.clone(),
+            JoinArgs::new(JoinType::Anti),
+        );
+    }
+    let removed_keys_df = removed_residual_lf
+        .select(keys.clone())
+        .limit(50)
+        .collect()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let mut added_residual_lf = lf_b.clone().join(
+        lf_a.clone(),
+        keys.clone(),
+        keys.clone(),
+        JoinArgs::new(JoinType::Anti),
+    );
+    if let Some(matched_keys) = &fuzzy_matched_added_keys {
+        added_residual_lf = added_residual_lf.join(
+            matched_keys.clone().lazy(),
+            keys.clone(),
+            keys.clone(),
+            JoinArgs::new(JoinType::Anti),
+        );
+    }
+    let added_keys_df = added_residual_lf
+        .select(keys.clone())
+        .limit(50)
+        .collect()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let key_rows_to_dicts = |df: &DataFrame| -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let mut out = Vec::with_capacity(df.height());
+        for i in 0..df.height() {
+            let row = PyDict::new(py);
+            for k in &keys_strs {
+                let v = df
+                    .column(k)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+                    .get(i)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+                row.set_item(*k, format!("{}", v))?;
+            }
+            out.push(row);
+        }
+        Ok(out)
+    };
+
+    let removed_sample_keys = key_rows_to_dicts(&removed_keys_df)?;
+    let added_sample_keys = key_rows_to_dicts(&added_keys_df)?;
+
     // 2.4 Global Sample Pass (Fetch samples for ALL columns in one pass)
-    let global_samples = if let Some(mask) = total_modified_mask {
+    let global_samples = if let Some(mask) = &total_modified_mask {
         joined_lf
             .clone()
-            .filter(mask)
+            .filter(mask.clone())
             .limit(100) // Fetch up to 100 modified rows once
             .collect()
             .ok()
@@ -245,6 +711,44 @@ fn diff_files<'py>(
         None
     };
 
+    // 2.4.1 Full Row-Level Diff Export (optional)
+    // Streams the complete diff -- not just the 100-row sample above -- to
+    // disk via the lazy/streaming engine so multi-gigabyte diffs don't have
+    // to be materialized in memory.
+    if let Some(out_path) = &output_path {
+        let full_diff_lf = build_diff_export_lf(
+            joined_lf.clone(),
+            lf_a.clone(),
+            lf_b.clone(),
+            &schema_a,
+            &schema_b,
+            &keys,
+            &keys_strs,
+            total_modified_mask.clone(),
+        )
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+        .with_new_streaming(true);
+
+        if out_path.ends_with(".parquet") || out_path.ends_with(".pq") {
+            full_diff_lf
+                .sink_parquet(out_path.into(), Default::default())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        } else if out_path.ends_with(".csv") {
+            full_diff_lf
+                .sink_csv(out_path.into(), Default::default())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        } else if out_path.ends_with(".jsonl") || out_path.ends_with(".ndjson") {
+            full_diff_lf
+                .sink_ndjson(out_path.into(), Default::default())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unsupported output_path extension for {:?}: expected .parquet/.pq/.csv/.jsonl/.ndjson",
+                out_path
+            )));
+        }
+    }
+
     // 2.5 Assemble Stats Dictionary
     let column_stats = PyDict::new(py);
     for (col_name, dtype_a) in schema_a.iter() {
@@ -347,6 +851,141 @@ fn diff_files<'py>(
         column_stats.set_item(name_str, stats)?;
     }
 
+    // 2.5.1 Temporal-Windowed Comparison (optional)
+    // Buckets both frames into time windows and diffs the bucketed aggregates
+    // instead of row keys -- useful for append-only event logs where exact
+    // row identity drifts but the windowed totals should still line up.
+    let window_diffs = if let Some(tc) = &time_col {
+        let every = window_every
+            .as_deref()
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "window_every is required when time_col is set",
+                )
+            })?;
+        let period = window_period.as_deref().unwrap_or(every);
+        let offset = window_offset.as_deref().unwrap_or("0s");
+        let closed = match window_closed.as_deref().unwrap_or("left") {
+            "left" => ClosedWindow::Left,
+            "right" => ClosedWindow::Right,
+            "both" => ClosedWindow::Both,
+            "none" => ClosedWindow::None,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "invalid window_closed {:?}: expected one of left/right/both/none",
+                    other
+                )))
+            }
+        };
+
+        Some(compute_window_diffs(
+            py, lf_a.clone(), lf_b.clone(), &schema_a, &schema_b, tc, every, period, offset,
+            closed,
+        )?)
+    } else {
+        None
+    };
+
+    // 2.6 Per-Partition Breakdown (optional)
+    // Groups the matched/added/removed counts by the caller-supplied partition
+    // columns, e.g. so "region=us added 400 rows" can be reported instead of
+    // just one global number.
+    let partition_stats = if let Some(part_cols) = &partition_cols {
+        let part_exprs: Vec<Expr> = part_cols.iter().map(|s| col(s.as_str())).collect();
+
+        let matched_by_part = joined_lf
+            .clone()
+            .group_by(part_exprs.clone())
+            .agg([
+                len().alias("matched"),
+                total_modified_mask
+                    .clone()
+                    .unwrap_or_else(|| lit(false))
+                    .cast(DataType::Float64)
+                    .sum()
+                    .alias("modified"),
+            ])
+            .collect()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        let total_a_by_part = lf_a
+            .clone()
+            .group_by(part_exprs.clone())
+            .agg([len().alias("total_a")])
+            .collect()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        let total_b_by_part = lf_b
+            .clone()
+            .group_by(part_exprs.clone())
+            .agg([len().alias("total_b")])
+            .collect()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        let merged = total_a_by_part
+            .join(
+                &total_b_by_part,
+                part_cols.as_slice(),
+                part_cols.as_slice(),
+                JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+                None,
+            )
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+            .join(
+                &matched_by_part,
+                part_cols.as_slice(),
+                part_cols.as_slice(),
+                JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+                None,
+            )
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        let mut rows = Vec::with_capacity(merged.height());
+        for i in 0..merged.height() {
+            let row = PyDict::new(py);
+            for pc in part_cols {
+                let v = merged
+                    .column(pc)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+                    .get(i)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+                row.set_item(pc, format!("{}", v))?;
+            }
+            let total_a_v = merged
+                .column("total_a")
+                .and_then(|c| c.get(i))
+                .ok()
+                .and_then(|v| v.try_extract::<f64>().ok())
+                .unwrap_or(0.0) as i64;
+            let total_b_v = merged
+                .column("total_b")
+                .and_then(|c| c.get(i))
+                .ok()
+                .and_then(|v| v.try_extract::<f64>().ok())
+                .unwrap_or(0.0) as i64;
+            let matched_v = merged
+                .column("matched")
+                .and_then(|c| c.get(i))
+                .ok()
+                .and_then(|v| v.try_extract::<f64>().ok())
+                .unwrap_or(0.0) as i64;
+            let modified_v = merged
+                .column("modified")
+                .and_then(|c| c.get(i))
+                .ok()
+                .and_then(|v| v.try_extract::<f64>().ok())
+                .unwrap_or(0.0) as i64;
+
+            row.set_item("added", (total_b_v - matched_v).max(0))?;
+            row.set_item("removed", (total_a_v - matched_v).max(0))?;
+            row.set_item("modified", modified_v)?;
+            rows.push(row);
+        }
+        Some(rows)
+    } else {
+        None
+    };
+
     // --- Final Assembly ---
     let dict = pyo3::types::PyDict::new(py);
     dict.set_item("total_rows_a", height_a)?;
@@ -356,14 +995,689 @@ fn diff_files<'py>(
     dict.set_item("modified_rows_count", modified_rows_count)?;
     dict.set_item("added", added)?;
     dict.set_item("removed", removed)?;
+    dict.set_item("added_sample_keys", added_sample_keys)?;
+    dict.set_item("removed_sample_keys", removed_sample_keys)?;
+    dict.set_item("fuzzy_reclassified_count", fuzzy_reclassified_count)?;
     dict.set_item("column_stats", column_stats)?;
+    dict.set_item("partition_stats", partition_stats)?;
+    dict.set_item("window_diffs", window_diffs)?;
 
     Ok(dict)
 }
 
+/// Buckets `lf_a`/`lf_b` into dynamic time windows over `time_col` and
+/// returns, per window start, the row-count delta and the drift in the mean
+/// of every shared numeric column.
+fn compute_window_diffs<'py>(
+    py: Python<'py>,
+    lf_a: LazyFrame,
+    lf_b: LazyFrame,
+    schema_a: &Schema,
+    schema_b: &Schema,
+    time_col: &str,
+    every: &str,
+    period: &str,
+    offset: &str,
+    closed: ClosedWindow,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let numeric_cols: Vec<String> = schema_a
+        .iter()
+        .filter(|(name, dtype)| {
+            name.as_str() != time_col && dtype.is_numeric() && schema_b.contains(name.as_str())
+        })
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    let mut aggs = vec![len().alias("_count")];
+    for c in &numeric_cols {
+        aggs.push(col(c.as_str()).mean().alias(&format!("{}_mean", c)));
+    }
+
+    let dyn_opts = DynamicGroupOptions {
+        index_column: time_col.into(),
+        every: Duration::parse(every),
+        period: Duration::parse(period),
+        offset: Duration::parse(offset),
+        closed_window: closed,
+        ..Default::default()
+    };
+
+    let windows_a = lf_a
+        .sort([time_col], Default::default())
+        .group_by_dynamic(col(time_col), [], dyn_opts.clone())
+        .agg(aggs.clone())
+        .collect()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let windows_b = lf_b
+        .sort([time_col], Default::default())
+        .group_by_dynamic(col(time_col), [], dyn_opts)
+        .agg(aggs)
+        .collect()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let merged = windows_a
+        .join(
+            &windows_b,
+            [time_col],
+            [time_col],
+            JoinArgs::new(JoinType::Full)
+                .with_coalesce(JoinCoalesce::CoalesceColumns)
+                .with_suffix(Some("_b".into())),
+            None,
+        )
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let get_f64 = |col_name: &str, i: usize| -> f64 {
+        merged
+            .column(col_name)
+            .and_then(|c| c.get(i))
+            .ok()
+            .and_then(|v| v.try_extract::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+
+    let mut out = Vec::with_capacity(merged.height());
+    for i in 0..merged.height() {
+        let row = PyDict::new(py);
+        let window_start = merged
+            .column(time_col)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+            .get(i)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        row.set_item("window_start", format!("{}", window_start))?;
+
+        let count_a = get_f64("_count", i);
+        let count_b = get_f64("_count_b", i);
+        row.set_item("rows_added", (count_b - count_a).max(0.0) as i64)?;
+        row.set_item("rows_removed", (count_a - count_b).max(0.0) as i64)?;
+
+        let mean_drift = PyDict::new(py);
+        for c in &numeric_cols {
+            let mean_a = get_f64(&format!("{}_mean", c), i);
+            let mean_b = get_f64(&format!("{}_mean_b", c), i);
+            mean_drift.set_item(c, mean_b - mean_a)?;
+        }
+        row.set_item("mean_drift", mean_drift)?;
+        out.push(row);
+    }
+    Ok(out)
+}
+
+/// Normalized edit-distance similarity in `[0, 1]`; `1.0` means identical.
+fn string_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+    let (m, n) = (a_chars.len(), b_chars.len());
+    if m == 0 || n == 0 {
+        return if m == n { 1.0 } else { 0.0 };
+    }
+
+    let mut prev: Vec<usize> = (0..=n).collect();
+    let mut curr = vec![0usize; n + 1];
+    for i in 1..=m {
+        curr[0] = i;
+        for j in 1..=n {
+            let cost = if a_chars[i - 1] == b_chars[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    1.0 - (prev[n] as f64) / (m.max(n) as f64)
+}
+
+/// Relative closeness of two numbers in `[0, 1]`; `1.0` means identical.
+fn numeric_closeness(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() {
+        return 0.0;
+    }
+    let denom = a.abs().max(b.abs()).max(1e-9);
+    (1.0 - (a - b).abs() / denom).max(0.0)
+}
+
+struct FuzzyMatch {
+    reclassified: usize,
+    /// Row indices into `removed_df`/`added_df` that were reclassified as
+    /// `modified`, so callers can exclude them from anything derived from
+    /// the raw residuals (e.g. added/removed key samples).
+    removed_idx: Vec<usize>,
+    added_idx: Vec<usize>,
+}
+
+/// Greedily pairs A-only (`removed_df`) and B-only (`added_df`) residual rows
+/// that share a `block_col` value, scoring each candidate pair on the
+/// fraction of shared non-key columns that are "equal enough", and accepting
+/// the highest-scoring pairs at or above `threshold` (each row consumed once).
+fn fuzzy_match_residuals(
+    removed_df: &DataFrame,
+    added_df: &DataFrame,
+    compare_cols: &[(String, DataType)],
+    block_col: &str,
+    threshold: f64,
+) -> PyResult<FuzzyMatch> {
+    if compare_cols.is_empty() {
+        return Ok(FuzzyMatch {
+            reclassified: 0,
+            removed_idx: Vec::new(),
+            added_idx: Vec::new(),
+        });
+    }
+
+    let block_key = |df: &DataFrame, i: usize| -> String {
+        df.column(block_col)
+            .ok()
+            .and_then(|c| c.get(i).ok())
+            .map(|v| format!("{}", v))
+            .unwrap_or_default()
+    };
+
+    let mut removed_blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for i in 0..removed_df.height() {
+        removed_blocks
+            .entry(block_key(removed_df, i))
+            .or_default()
+            .push(i);
+    }
+    let mut added_blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for i in 0..added_df.height() {
+        added_blocks
+            .entry(block_key(added_df, i))
+            .or_default()
+            .push(i);
+    }
+
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (block, removed_idxs) in &removed_blocks {
+        let Some(added_idxs) = added_blocks.get(block) else {
+            continue;
+        };
+        for &ri in removed_idxs {
+            for &ai in added_idxs {
+                let mut matches = 0usize;
+                for (name, dtype) in compare_cols {
+                    let va = match removed_df.column(name).and_then(|c| c.get(ri)) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    let vb = match added_df.column(name).and_then(|c| c.get(ai)) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    let is_match = if dtype.is_numeric() {
+                        let fa = va.try_extract::<f64>().unwrap_or(f64::NAN);
+                        let fb = vb.try_extract::<f64>().unwrap_or(f64::NAN);
+                        numeric_closeness(fa, fb) >= 0.99
+                    } else {
+                        string_similarity(&format!("{}", va), &format!("{}", vb)) >= 0.85
+                    };
+                    if is_match {
+                        matches += 1;
+                    }
+                }
+                let score = matches as f64 / compare_cols.len() as f64;
+                if score >= threshold {
+                    candidates.push((score, ri, ai));
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut used_removed: HashSet<usize> = HashSet::new();
+    let mut used_added: HashSet<usize> = HashSet::new();
+    for (_, ri, ai) in candidates {
+        if used_removed.contains(&ri) || used_added.contains(&ai) {
+            continue;
+        }
+        used_removed.insert(ri);
+        used_added.insert(ai);
+    }
+
+    Ok(FuzzyMatch {
+        reclassified: used_removed.len(),
+        removed_idx: used_removed.into_iter().collect(),
+        added_idx: used_added.into_iter().collect(),
+    })
+}
+
+/// Builds a small eager `DataFrame` of just `keys_strs` for the rows at
+/// `idxs` in `df`. Used to anti-join fuzzy-reclassified rows back out of the
+/// added/removed key samples so they agree with the post-fuzzy counts.
+fn key_subset_df(df: &DataFrame, idxs: &[usize], keys_strs: &[&str]) -> PolarsResult<DataFrame> {
+    let columns = keys_strs
+        .iter()
+        .map(|k| {
+            let col = df.column(k)?;
+            let values: Vec<AnyValue> = idxs.iter().map(|&i| col.get(i)).collect::<PolarsResult<_>>()?;
+            Ok(Series::from_any_values(k, &values, false)?.into())
+        })
+        .collect::<PolarsResult<Vec<Column>>>()?;
+    DataFrame::new(columns)
+}
+
 /// A Python module implemented in Rust.
 #[pymodule]
 fn _internal(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(diff_files, m)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod fuzzy_match_tests {
+    use super::*;
+
+    #[test]
+    fn string_similarity_identical_is_one() {
+        assert_eq!(string_similarity("abc", "abc"), 1.0);
+    }
+
+    #[test]
+    fn string_similarity_both_empty_is_one() {
+        assert_eq!(string_similarity("", ""), 1.0);
+    }
+
+    #[test]
+    fn string_similarity_one_empty_is_zero() {
+        assert_eq!(string_similarity("", "abc"), 0.0);
+    }
+
+    #[test]
+    fn string_similarity_known_edit_distance() {
+        // "kitten" -> "sitting" is edit distance 3 over a max length of 7.
+        let sim = string_similarity("kitten", "sitting");
+        assert!((sim - (1.0 - 3.0 / 7.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn numeric_closeness_identical_is_one() {
+        assert_eq!(numeric_closeness(5.0, 5.0), 1.0);
+    }
+
+    #[test]
+    fn numeric_closeness_far_apart_is_zero() {
+        assert_eq!(numeric_closeness(1.0, 1000.0), 0.0);
+    }
+
+    #[test]
+    fn numeric_closeness_nan_is_zero() {
+        assert_eq!(numeric_closeness(f64::NAN, 1.0), 0.0);
+    }
+
+    fn residual_df(region: &[&str], name: &[&str], amount: &[f64]) -> DataFrame {
+        df!(
+            "region" => region,
+            "name" => name,
+            "amount" => amount,
+        )
+        .unwrap()
+    }
+
+    fn compare_cols() -> Vec<(String, DataType)> {
+        vec![
+            ("name".to_string(), DataType::String),
+            ("amount".to_string(), DataType::Float64),
+        ]
+    }
+
+    #[test]
+    fn fuzzy_match_pairs_near_duplicate_within_block() {
+        // Same block ("us"), near-identical name and amount -> should pair.
+        let removed = residual_df(&["us"], &["acme corp"], &[100.0]);
+        let added = residual_df(&["us"], &["acme corp."], &[100.0]);
+
+        let result =
+            fuzzy_match_residuals(&removed, &added, &compare_cols(), "region", 0.5).unwrap();
+        assert_eq!(result.reclassified, 1);
+    }
+
+    #[test]
+    fn fuzzy_match_does_not_pair_across_blocks() {
+        // Identical non-key columns, but different blocking values -> no pair.
+        let removed = residual_df(&["us"], &["acme corp"], &[100.0]);
+        let added = residual_df(&["eu"], &["acme corp"], &[100.0]);
+
+        let result =
+            fuzzy_match_residuals(&removed, &added, &compare_cols(), "region", 0.5).unwrap();
+        assert_eq!(result.reclassified, 0);
+    }
+
+    #[test]
+    fn fuzzy_match_below_threshold_is_not_paired() {
+        let removed = residual_df(&["us"], &["acme corp"], &[100.0]);
+        let added = residual_df(&["us"], &["totally different"], &[1.0]);
+
+        let result =
+            fuzzy_match_residuals(&removed, &added, &compare_cols(), "region", 0.9).unwrap();
+        assert_eq!(result.reclassified, 0);
+    }
+
+    #[test]
+    fn fuzzy_match_greedily_consumes_each_row_once() {
+        // Two removed rows both candidate-match the same single added row;
+        // only the higher-scoring pair should be accepted.
+        let removed = residual_df(&["us", "us"], &["acme corp", "acme corpx"], &[100.0, 90.0]);
+        let added = residual_df(&["us"], &["acme corp"], &[100.0]);
+
+        let result =
+            fuzzy_match_residuals(&removed, &added, &compare_cols(), "region", 0.5).unwrap();
+        assert_eq!(result.reclassified, 1);
+        assert_eq!(result.removed_idx, vec![0]);
+    }
+}
+
+#[cfg(test)]
+mod tolerance_tests {
+    use super::*;
+
+    /// Evaluates `build_is_diff_expr` against a single (`a`, `b`) row and
+    /// returns whether it was flagged as a mismatch.
+    fn is_flagged_diff(a: AnyValue, b: AnyValue, dtype: &DataType, rule: Option<&ToleranceRule>) -> bool {
+        let df = DataFrame::new(vec![
+            Series::from_any_values("val", &[a], false).unwrap().into(),
+            Series::from_any_values("val_right", &[b], false).unwrap().into(),
+        ])
+        .unwrap();
+
+        let expr = build_is_diff_expr("val", "val_right", dtype, dtype, rule);
+        let result = df
+            .lazy()
+            .select([expr.alias("is_diff")])
+            .collect()
+            .unwrap();
+        result
+            .column("is_diff")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .get(0)
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn numeric_no_rule_is_strict() {
+        let flagged = is_flagged_diff(
+            AnyValue::Float64(1.0),
+            AnyValue::Float64(1.0001),
+            &DataType::Float64,
+            None,
+        );
+        assert!(flagged);
+    }
+
+    #[test]
+    fn numeric_absolute_tolerance_suppresses_small_drift() {
+        let rule = ToleranceRule::Absolute(0.01);
+        let flagged = is_flagged_diff(
+            AnyValue::Float64(1.0),
+            AnyValue::Float64(1.0001),
+            &DataType::Float64,
+            Some(&rule),
+        );
+        assert!(!flagged);
+    }
+
+    #[test]
+    fn numeric_absolute_tolerance_still_flags_large_drift() {
+        let rule = ToleranceRule::Absolute(0.01);
+        let flagged = is_flagged_diff(
+            AnyValue::Float64(1.0),
+            AnyValue::Float64(5.0),
+            &DataType::Float64,
+            Some(&rule),
+        );
+        assert!(flagged);
+    }
+
+    #[test]
+    fn numeric_relative_tolerance_scales_with_magnitude() {
+        let rule = ToleranceRule::AbsRelative(0.0, 0.1);
+        // rtol applies to abs(b): 10% of 1050 is 105, so a drift of 50 should be within tolerance...
+        let flagged_small = is_flagged_diff(
+            AnyValue::Float64(1000.0),
+            AnyValue::Float64(1050.0),
+            &DataType::Float64,
+            Some(&rule),
+        );
+        assert!(!flagged_small);
+        // ...but a drift of 500 should not be.
+        let flagged_large = is_flagged_diff(
+            AnyValue::Float64(1000.0),
+            AnyValue::Float64(1500.0),
+            &DataType::Float64,
+            Some(&rule),
+        );
+        assert!(flagged_large);
+    }
+
+    #[test]
+    fn string_ignore_case_and_whitespace_are_normalized() {
+        let rule = ToleranceRule::StringNormalize {
+            trim: true,
+            ignore_case: true,
+        };
+        let flagged = is_flagged_diff(
+            AnyValue::String("Acme Corp"),
+            AnyValue::String(" acme corp "),
+            &DataType::String,
+            Some(&rule),
+        );
+        assert!(!flagged);
+    }
+
+    #[test]
+    fn string_without_rule_is_strict() {
+        let flagged = is_flagged_diff(
+            AnyValue::String("Acme Corp"),
+            AnyValue::String("acme corp"),
+            &DataType::String,
+            None,
+        );
+        assert!(flagged);
+    }
+}
+
+#[cfg(test)]
+mod window_tests {
+    use super::*;
+
+    /// A timestamp landing exactly on the first window boundary must still
+    /// fall into a window instead of being silently dropped -- this is the
+    /// reason `closed` defaults to left-closed.
+    #[test]
+    fn earliest_boundary_timestamp_is_not_dropped() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let df = df!(
+                "ts" => [0i64, 2, 4],
+                "val" => [10.0, 20.0, 30.0],
+            )
+            .unwrap();
+            let mut lf_a = df.clone().lazy();
+            let mut lf_b = df.lazy();
+            let schema_a = lf_a.collect_schema().unwrap();
+            let schema_b = lf_b.collect_schema().unwrap();
+
+            let windows = compute_window_diffs(
+                py,
+                lf_a,
+                lf_b,
+                &schema_a,
+                &schema_b,
+                "ts",
+                "2i",
+                "2i",
+                "0i",
+                ClosedWindow::Left,
+            )
+            .unwrap();
+
+            assert_eq!(windows.len(), 3, "expected one window per 2-wide bucket");
+            let first_start = windows[0].get_item("window_start").unwrap().unwrap();
+            assert_eq!(first_start.to_string(), "0");
+        });
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    /// `_change_type` must land on the right rows, and a column that only
+    /// exists in B (schema drift) must still show up as `{col}_b` on a
+    /// matched row -- not just on the `added` rows where it originated.
+    #[test]
+    fn change_type_assignment_and_b_only_column_on_matched_row() {
+        let df_a = df!(
+            "id" => [1i64, 2],
+            "amount" => [10.0, 20.0],
+            "only_a" => ["xa1", "xa2"],
+        )
+        .unwrap();
+        let df_b = df!(
+            "id" => [1i64, 3],
+            "amount" => [99.0, 30.0],
+            "only_b" => ["xb1", "xb3"],
+        )
+        .unwrap();
+
+        let mut lf_a = df_a.lazy();
+        let mut lf_b = df_b.lazy();
+        let schema_a = lf_a.collect_schema().unwrap();
+        let schema_b = lf_b.collect_schema().unwrap();
+
+        let keys = vec![col("id")];
+        let keys_strs = vec!["id"];
+
+        let joined_lf = lf_a.clone().join(
+            lf_b.clone(),
+            keys.clone(),
+            keys.clone(),
+            JoinArgs::new(JoinType::Inner).with_suffix(Some("_right".into())),
+        );
+        let is_diff = build_is_diff_expr(
+            "amount",
+            "amount_right",
+            &DataType::Float64,
+            &DataType::Float64,
+            None,
+        );
+
+        let result = build_diff_export_lf(
+            joined_lf,
+            lf_a,
+            lf_b,
+            &schema_a,
+            &schema_b,
+            &keys,
+            &keys_strs,
+            Some(is_diff),
+        )
+        .unwrap()
+        .collect()
+        .unwrap();
+
+        let mut change_types: Vec<Option<&str>> =
+            result.column("_change_type").unwrap().str().unwrap().into_iter().collect();
+        change_types.sort();
+        assert_eq!(change_types, vec![Some("added"), Some("modified"), Some("removed")]);
+
+        let matched_row = result
+            .clone()
+            .lazy()
+            .filter(col("_change_type").eq(lit("modified")))
+            .collect()
+            .unwrap();
+        assert_eq!(matched_row.height(), 1);
+        assert_eq!(
+            matched_row.column("only_b_b").unwrap().str().unwrap().get(0),
+            Some("xb1")
+        );
+    }
+}
+
+#[cfg(test)]
+mod composite_key_tests {
+    use super::*;
+
+    /// Mirrors the null-filled `concat_str(.., "\u{1f}", false)` + `n_unique()`
+    /// expression `get_meta` uses to measure composite-key uniqueness. A
+    /// composite key can collide on its *first* column while still being
+    /// unique overall -- that must not be reported as a duplicate key.
+    #[test]
+    fn composite_key_unique_despite_first_column_collision() {
+        let df = df!(
+            "region" => ["us", "us", "eu"],
+            "id" => [1i64, 2, 1],
+        )
+        .unwrap();
+
+        let key_parts: Vec<Expr> = [col("region"), col("id")]
+            .into_iter()
+            .map(|e| e.cast(DataType::String).fill_null(lit("\u{0}")))
+            .collect();
+        let composite_key = concat_str(key_parts, "\u{1f}", false);
+        let result = df
+            .lazy()
+            .select([len().alias("total"), composite_key.n_unique().alias("unique")])
+            .collect()
+            .unwrap();
+
+        let total = result
+            .column("total")
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .try_extract::<u32>()
+            .unwrap();
+        let unique = result
+            .column("unique")
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .try_extract::<u32>()
+            .unwrap();
+
+        assert_eq!(total, 3);
+        assert_eq!(
+            unique, 3,
+            "rows colliding only on the first key column must not look duplicated"
+        );
+    }
+}
+
+#[cfg(test)]
+mod scan_dispatch_tests {
+    use super::*;
+
+    #[test]
+    fn only_parquet_globs_are_partitioned_datasets() {
+        let cases = [
+            ("data/part-*.parquet", true),
+            ("data/part-*.pq", true),
+            ("data/part-0.parquet", false),
+            ("events_*.csv", false),
+            ("events_*.jsonl", false),
+            ("events_*.json", false),
+            ("events.csv", false),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                is_partitioned_dataset(path),
+                expected,
+                "is_partitioned_dataset({:?}) should be {}",
+                path,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn an_actual_directory_is_a_partitioned_dataset() {
+        let dir = std::env::temp_dir();
+        assert!(is_partitioned_dataset(dir.to_str().unwrap()));
+    }
+}